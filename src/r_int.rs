@@ -1,27 +1,43 @@
+use std::cmp::Ordering;
 use std::fmt;
 use std::ops::Add;
 use std::ops::Div;
 use std::ops::Mul;
+use std::ops::Rem;
 use std::ops::Sub;
+use std::str::FromStr;
 
-#[derive(Debug)]
+use num_traits::{Bounded, CheckedAdd, CheckedMul, CheckedSub, Num, One, Saturating, Zero};
+
+// BITSビット幅の2の補数表現を、u64の下位BITSビットに格納する（BITSは1〜64のみ対応）
+#[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
-pub struct RInt {
-    bits: u32,
+pub struct RInt<const BITS: usize> {
+    bits: u64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum ParseError {
-    InvalidDigit,
-    Overflow,
+    // 空文字列、あるいは符号のみ（"+"や"-"）の入力
+    Empty,
+    // byteが何桁目（index）で見つかったかを保持する
+    InvalidDigit { byte: u8, index: usize },
+    // 正の方向にオーバーフローした
+    PosOverflow,
+    // 負の方向にオーバーフローした
+    NegOverflow,
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ParseError::InvalidDigit => write!(f, "Invalid digit found"),
-            ParseError::Overflow => write!(f, "Overflow occurred"),
+            ParseError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseError::InvalidDigit { byte, index } => {
+                write!(f, "invalid digit '{}' at position {}", *byte as char, index)
+            }
+            ParseError::PosOverflow => write!(f, "number too large to fit in target type"),
+            ParseError::NegOverflow => write!(f, "number too small to fit in target type"),
         }
     }
 }
@@ -36,60 +52,404 @@ enum State {
     InFraction,
 }
 
-impl Add for RInt {
+impl<const BITS: usize> Add for RInt<BITS> {
     type Output = Self;
 
     // + 演算子をオーバーロードするためにはAddトレイトを実装する必要があり、Resultは返せないため泣く泣くwrapping_addにしている
     fn add(self, other: Self) -> Self {
-        RInt {
-            bits: self.bits.wrapping_add(other.bits),
-        }
+        self.wrapping_add(other)
     }
 }
 
-impl Sub for RInt {
+impl<const BITS: usize> Sub for RInt<BITS> {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        RInt {
-            bits: self.bits.wrapping_sub(other.bits),
-        }
+        self.wrapping_sub(other)
     }
 }
 
-impl Mul for RInt {
+impl<const BITS: usize> Mul for RInt<BITS> {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
-        RInt {
-            bits: self.bits.wrapping_mul(other.bits),
-        }
+        self.wrapping_mul(other)
     }
 }
 
-impl Div for RInt {
+impl<const BITS: usize> Div for RInt<BITS> {
     type Output = Self;
 
+    // 符号ビットを見て絶対値同士で除算してから符号を付け直す（0方向への切り捨て、Rustのi32と同じ挙動）
     fn div(self, other: Self) -> Self {
-        RInt {
-            bits: self.bits.wrapping_div(other.bits),
+        if other.bits == 0 {
+            panic!("attempt to divide by zero");
+        }
+        if self.bits == Self::MIN && other.bits == Self::MASK {
+            // MIN / -1 は符号付きの範囲に収まらずオーバーフローするので、i32と同じく無条件にパニックする
+            panic!("attempt to divide with overflow");
+        }
+
+        let self_negative = self.bits & Self::SIGN_BIT != 0;
+        let other_negative = other.bits & Self::SIGN_BIT != 0;
+
+        let self_abs = if self_negative {
+            Self::negate(self.bits)
+        } else {
+            self.bits
+        };
+        let other_abs = if other_negative {
+            Self::negate(other.bits)
+        } else {
+            other.bits
+        };
+
+        let quotient = self_abs / other_abs;
+
+        let bits = if self_negative != other_negative {
+            Self::negate(quotient)
+        } else {
+            quotient
+        };
+
+        RInt { bits }
+    }
+}
+
+impl<const BITS: usize> Rem for RInt<BITS> {
+    type Output = Self;
+
+    // (a / b) * b + (a % b) == a となるように、余りの符号は被除数(self)の符号に合わせる
+    fn rem(self, other: Self) -> Self {
+        if other.bits == 0 {
+            panic!("attempt to calculate the remainder with a divisor of zero");
         }
+
+        let self_negative = self.bits & Self::SIGN_BIT != 0;
+        let other_negative = other.bits & Self::SIGN_BIT != 0;
+
+        let self_abs = if self_negative {
+            Self::negate(self.bits)
+        } else {
+            self.bits
+        };
+        let other_abs = if other_negative {
+            Self::negate(other.bits)
+        } else {
+            other.bits
+        };
+
+        let remainder = self_abs % other_abs;
+
+        let bits = if self_negative {
+            Self::negate(remainder)
+        } else {
+            remainder
+        };
+
+        RInt { bits }
+    }
+}
+
+impl<const BITS: usize> PartialEq for RInt<BITS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl<const BITS: usize> Eq for RInt<BITS> {}
+
+impl<const BITS: usize> PartialOrd for RInt<BITS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const BITS: usize> Ord for RInt<BITS> {
+    // 符号ビットを解釈して比較する必要があるため、符号拡張したi128として比較する
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.signed_value().cmp(&other.signed_value())
+    }
+}
+
+// num-traitsのジェネリックな数値境界（Num等）でRIntを使えるようにするための委譲実装
+impl<const BITS: usize> FromStr for RInt<BITS> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RInt::from_str(s)
+    }
+}
+
+impl<const BITS: usize> fmt::Display for RInt<BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.signed_value())
+    }
+}
+
+impl<const BITS: usize> Zero for RInt<BITS> {
+    fn zero() -> Self {
+        // from_bits経由でMASKを参照させ、無効なBITSをここでも検査させる
+        Self::from_bits(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.bits == 0
+    }
+}
+
+impl<const BITS: usize> One for RInt<BITS> {
+    fn one() -> Self {
+        Self::from_bits(1)
+    }
+}
+
+impl<const BITS: usize> Bounded for RInt<BITS> {
+    fn min_value() -> Self {
+        RInt { bits: Self::MIN }
+    }
+
+    fn max_value() -> Self {
+        RInt { bits: Self::MAX }
+    }
+}
+
+impl<const BITS: usize> Num for RInt<BITS> {
+    type FromStrRadixErr = ParseError;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        RInt::from_str_radix(str, radix)
+    }
+}
+
+impl<const BITS: usize> CheckedAdd for RInt<BITS> {
+    fn checked_add(&self, v: &Self) -> Option<Self> {
+        (*self).checked_add(*v)
+    }
+}
+
+impl<const BITS: usize> CheckedSub for RInt<BITS> {
+    fn checked_sub(&self, v: &Self) -> Option<Self> {
+        (*self).checked_sub(*v)
+    }
+}
+
+impl<const BITS: usize> CheckedMul for RInt<BITS> {
+    fn checked_mul(&self, v: &Self) -> Option<Self> {
+        (*self).checked_mul(*v)
+    }
+}
+
+impl<const BITS: usize> Saturating for RInt<BITS> {
+    fn saturating_add(self, v: Self) -> Self {
+        self.saturating_add(v)
+    }
+
+    fn saturating_sub(self, v: Self) -> Self {
+        self.saturating_sub(v)
     }
 }
 
-impl RInt {
-    // 10進数表記の正数を32bitの正数型に変換する
+impl<const BITS: usize> RInt<BITS> {
+    // BITSが1〜64の範囲に収まっていることを検査する。MASK/SIGN_BITは必ずこれを経由して定義することで、
+    // 両者のどちらかを参照するだけで（let _ = ...;のような明示的な強制なしに）チェックが効くようにする
+    const CHECKED_BITS: usize = {
+        assert!(BITS >= 1 && BITS <= 64, "RInt only supports bit widths from 1 to 64");
+        BITS
+    };
+
+    const MASK: u64 = if Self::CHECKED_BITS == 64 {
+        u64::MAX
+    } else {
+        (1u64 << Self::CHECKED_BITS) - 1
+    };
+    const SIGN_BIT: u64 = 1u64 << (Self::CHECKED_BITS - 1);
+    const MIN: u64 = Self::SIGN_BIT & Self::MASK;
+    const MAX: u64 = Self::MASK & !Self::SIGN_BIT;
+
+    // BITSビットの2の補数表現として反転して1を足す（上位の余分なビットはMASKで落とす）
+    fn negate(bits: u64) -> u64 {
+        (!bits).wrapping_add(1) & Self::MASK
+    }
+
+    // 符号ビットを見てi128に符号拡張する（比較や乗算オーバーフロー判定に使う）
+    fn signed_value(&self) -> i128 {
+        if self.bits & Self::SIGN_BIT != 0 {
+            self.bits as i128 - (Self::MASK as i128 + 1)
+        } else {
+            self.bits as i128
+        }
+    }
+
+    // 基本はデバッグやテスト用かな
+    pub fn from_bits(bits: u64) -> Self {
+        RInt {
+            bits: bits & Self::MASK,
+        }
+    }
+
+    // 今までのAdd/Sub/Mul/Divが行っていたラップアラウンドの挙動に明示的な名前を付けたもの
+    pub fn wrapping_add(self, other: Self) -> Self {
+        RInt {
+            bits: self.bits.wrapping_add(other.bits) & Self::MASK,
+        }
+    }
+
+    pub fn wrapping_sub(self, other: Self) -> Self {
+        RInt {
+            bits: self.bits.wrapping_sub(other.bits) & Self::MASK,
+        }
+    }
+
+    pub fn wrapping_mul(self, other: Self) -> Self {
+        RInt {
+            bits: self.bits.wrapping_mul(other.bits) & Self::MASK,
+        }
+    }
+
+    pub fn wrapping_div(self, other: Self) -> Self {
+        if self.bits == Self::MIN && other.bits == Self::MASK {
+            // MIN / -1 はオーバーフローするのでラップしてMIN自身を返す（i32と同じ挙動）
+            return RInt { bits: Self::MIN };
+        }
+        self / other
+    }
+
+    // 加算のオーバーフローは、両オペランドの符号が一致していて結果の符号がそれと異なる場合に発生する
+    pub fn overflowing_add(self, other: Self) -> (Self, bool) {
+        let self_sign = self.bits & Self::SIGN_BIT;
+        let other_sign = other.bits & Self::SIGN_BIT;
+        let bits = self.bits.wrapping_add(other.bits) & Self::MASK;
+        let result_sign = bits & Self::SIGN_BIT;
+
+        let overflow = self_sign == other_sign && result_sign != self_sign;
+        (RInt { bits }, overflow)
+    }
+
+    // 減算のオーバーフローは、オペランドの符号が異なっていて結果の符号がself側と異なる場合に発生する
+    pub fn overflowing_sub(self, other: Self) -> (Self, bool) {
+        let self_sign = self.bits & Self::SIGN_BIT;
+        let other_sign = other.bits & Self::SIGN_BIT;
+        let bits = self.bits.wrapping_sub(other.bits) & Self::MASK;
+        let result_sign = bits & Self::SIGN_BIT;
+
+        let overflow = self_sign != other_sign && result_sign != self_sign;
+        (RInt { bits }, overflow)
+    }
+
+    // i128に符号拡張して計算し、BITSビットの符号付き範囲に収まるかどうかでオーバーフローを判定する
+    pub fn overflowing_mul(self, other: Self) -> (Self, bool) {
+        let wide = self.signed_value() * other.signed_value();
+        let min = -(Self::SIGN_BIT as i128);
+        let max = Self::MAX as i128;
+        let overflow = wide < min || wide > max;
+        let bits = (wide as u64) & Self::MASK;
+        (RInt { bits }, overflow)
+    }
+
+    pub fn overflowing_div(self, other: Self) -> (Self, bool) {
+        if other.bits == 0 {
+            panic!("attempt to divide by zero");
+        }
+        if self.bits == Self::MIN && other.bits == Self::MASK {
+            // MIN / -1 だけが符号付きの範囲に収まらずオーバーフローする
+            return (RInt { bits: Self::MIN }, true);
+        }
+        (self / other, false)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        match self.overflowing_add(other) {
+            (result, false) => Some(result),
+            (_, true) => None,
+        }
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        match self.overflowing_sub(other) {
+            (result, false) => Some(result),
+            (_, true) => None,
+        }
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        match self.overflowing_mul(other) {
+            (result, false) => Some(result),
+            (_, true) => None,
+        }
+    }
+
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.bits == 0 {
+            return None;
+        }
+        match self.overflowing_div(other) {
+            (result, false) => Some(result),
+            (_, true) => None,
+        }
+    }
+
+    // オーバーフロー時は符号付きの範囲[MIN, MAX]の端にクランプする
+    pub fn saturating_add(self, other: Self) -> Self {
+        let self_sign = self.bits & Self::SIGN_BIT;
+        match self.overflowing_add(other) {
+            (result, false) => result,
+            (_, true) => {
+                if self_sign == 0 {
+                    RInt { bits: Self::MAX }
+                } else {
+                    RInt { bits: Self::MIN }
+                }
+            }
+        }
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        let self_sign = self.bits & Self::SIGN_BIT;
+        match self.overflowing_sub(other) {
+            (result, false) => result,
+            (_, true) => {
+                if self_sign == 0 {
+                    RInt { bits: Self::MAX }
+                } else {
+                    RInt { bits: Self::MIN }
+                }
+            }
+        }
+    }
+
+    pub fn saturating_mul(self, other: Self) -> Self {
+        let wide = self.signed_value() * other.signed_value();
+        let min = -(Self::SIGN_BIT as i128);
+        let max = Self::MAX as i128;
+        if wide > max {
+            RInt { bits: Self::MAX }
+        } else if wide < min {
+            RInt { bits: Self::MIN }
+        } else {
+            RInt {
+                bits: (wide as u64) & Self::MASK,
+            }
+        }
+    }
+
+    // 10進数表記の整数をBITSビットの表現に変換する
     // 2の補数表現を利用する（最上位ビットが符号ビット）
     // e.g.
-    // "5" -> 0b00000000000000000000000000000101
-    // "-5" -> 0b11111111111111111111111111110001
+    // "5" -> 0b101
+    // "-5" -> 符号ビットを含むBITSビット幅の2の補数表現
+    //
+    // FromStrと同名・同シグネチャの固有メソッドにしているのは、トレイトの実装がこれに委譲するため
+    // （呼び出し側はトレイトをuseしなくても使える）
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Result<Self, ParseError> {
         // Parse時のState
         let mut state = State::Start;
-        let mut bits: u32 = 0;
+        let mut bits: u64 = 0;
         let mut is_negative = false;
+        let mut saw_digit = false;
 
-        for &b in s.as_bytes() {
+        for (index, &b) in s.as_bytes().iter().enumerate() {
             match (state, b) {
                 (State::Start, b'+') | (State::Start, b'-') => {
                     state = State::InInteger;
@@ -99,10 +459,10 @@ impl RInt {
                 }
                 (State::Start | State::InInteger, b'0'..=b'9') => {
                     state = State::InInteger;
-                    let digit = b - b'0';
+                    saw_digit = true;
+                    let digit = (b - b'0') as u64;
                     // すでに入っている値を10倍して、新しい値を足す
-                    bits = bits.checked_mul(10).ok_or(ParseError::Overflow)?;
-                    bits = bits.checked_add(digit as u32).ok_or(ParseError::Overflow)?;
+                    bits = Self::checked_accumulate(bits, 10, digit, is_negative)?;
                 }
                 (State::InInteger, b'.') => {
                     // 小数点が見つかった場合は以降は小数部として処理
@@ -110,111 +470,304 @@ impl RInt {
                 }
                 (State::InFraction, b'0'..=b'9') => {
                     // 小数部の数値
-                    // 正数への変換なので特に何もしない
+                    // 整数への変換なので特に何もしない
                     // 数値が入っていた場合は自動的に切り捨ての形になる
                 }
                 _ => {
                     // 予期しない入力（数値以外など）があった場合は回復可能エラーを戻す
-                    return Err(ParseError::InvalidDigit);
+                    return Err(ParseError::InvalidDigit { byte: b, index });
                 }
             }
         }
 
+        if !saw_digit {
+            return Err(ParseError::Empty);
+        }
+
         if is_negative {
-            bits = !bits + 1; // 2の補数表現にするため反転して1を足す
+            bits = Self::negate(bits); // 2の補数表現にするため反転して1を足す
         }
 
-        return Ok(RInt { bits });
+        Ok(RInt { bits })
     }
 
-    // 基本はデバッグやテスト用かな
-    pub fn from_u32(bits: u32) -> Self {
-        RInt { bits }
+    // multiplier進の桁を1つ積み上げる。符号付きの範囲(正ならMAX、負ならSIGN_BITの大きさ)を超えたらオーバーフローエラーにする
+    fn checked_accumulate(
+        acc: u64,
+        multiplier: u64,
+        digit: u64,
+        is_negative: bool,
+    ) -> Result<u64, ParseError> {
+        let limit = if is_negative { Self::SIGN_BIT } else { Self::MAX };
+        acc.checked_mul(multiplier)
+            .and_then(|v| v.checked_add(digit))
+            .filter(|v| *v <= limit)
+            .ok_or_else(|| Self::overflow_error(is_negative))
     }
 
-    // 10進表記の文字列に変換する
-    pub fn to_string(&self) -> String {
-        let sign = self.bits & 0b1000_0000_0000_0000_0000_0000_0000_0000;
+    // オーバーフローが起きた時点での符号から、正負どちら方向のオーバーフローかを判定する
+    fn overflow_error(is_negative: bool) -> ParseError {
+        if is_negative {
+            ParseError::NegOverflow
+        } else {
+            ParseError::PosOverflow
+        }
+    }
+
+    // 2〜36進数の文字列を解釈する（libcoreのfrom_str_radixと同じ想定）
+    // 基数に一致する0x/0b/0oプレフィックスは符号の後ろにあれば読み飛ばす
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseError> {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in the range 2..=36"
+        );
+
+        let mut state = State::Start;
+        let mut bits: u64 = 0;
+        let mut saw_digit = false;
+
+        let prefix_len = match s.as_bytes().first() {
+            Some(b'+') | Some(b'-') => 1,
+            _ => 0,
+        };
+        let is_negative = s.as_bytes().first() == Some(&b'-');
+        let rest = &s[prefix_len..];
 
-        let value = if sign != 0 {
-            // 負数の場合
-            // 2の補数表現を解釈する
-            let value = !self.bits + 1;
-            // 10進数に変換
-            // ここは一旦自前実装なしでu32のto_stringを利用
-            // u32のto_stringは先頭ビットを解釈まではしてくれないのでそこは自分で対応
-            format!("-{}", value.to_string())
+        // 基数に一致する0x/0b/0oプレフィックスは読み飛ばす
+        let (rest, rest_index) = match (radix, rest.as_bytes()) {
+            (16, [b'0', b'x' | b'X', tail @ ..]) => (std::str::from_utf8(tail).unwrap(), 2),
+            (2, [b'0', b'b' | b'B', tail @ ..]) => (std::str::from_utf8(tail).unwrap(), 2),
+            (8, [b'0', b'o' | b'O', tail @ ..]) => (std::str::from_utf8(tail).unwrap(), 2),
+            _ => (rest, 0),
+        };
+
+        for (offset, &b) in rest.as_bytes().iter().enumerate() {
+            match (state, (b as char).to_digit(radix)) {
+                (State::Start | State::InInteger, Some(digit)) => {
+                    state = State::InInteger;
+                    saw_digit = true;
+                    bits = Self::checked_accumulate(bits, radix as u64, digit as u64, is_negative)?;
+                }
+                _ => {
+                    return Err(ParseError::InvalidDigit {
+                        byte: b,
+                        index: prefix_len + rest_index + offset,
+                    });
+                }
+            }
+        }
+
+        if !saw_digit {
+            return Err(ParseError::Empty);
+        }
+
+        let bits = if is_negative { Self::negate(bits) } else { bits };
+
+        Ok(RInt { bits })
+    }
+
+    // 固定小数点数として解釈する（整数部がBITS-frac_bitsビット、小数部がfrac_bitsビット）
+    // 小数部は10進の小数をfrac_bits桁の2進小数に丸める（四捨五入）
+    // e.g. frac_bits=16での"1.5" -> 整数部1、小数部0.5 * 2^16 = 0x8000
+    pub fn from_str_fixed(s: &str, frac_bits: u32) -> Result<Self, ParseError> {
+        let mut state = State::Start;
+        let mut int_part: u64 = 0;
+        let mut frac_digits: u64 = 0;
+        let mut frac_len: u32 = 0;
+        let mut is_negative = false;
+        let mut saw_digit = false;
+
+        for (index, &b) in s.as_bytes().iter().enumerate() {
+            match (state, b) {
+                (State::Start, b'+') | (State::Start, b'-') => {
+                    state = State::InInteger;
+                    if b == b'-' {
+                        is_negative = true;
+                    }
+                }
+                (State::Start | State::InInteger, b'0'..=b'9') => {
+                    state = State::InInteger;
+                    saw_digit = true;
+                    let digit = (b - b'0') as u64;
+                    int_part = int_part
+                        .checked_mul(10)
+                        .and_then(|v| v.checked_add(digit))
+                        .ok_or_else(|| Self::overflow_error(is_negative))?;
+                }
+                (State::InInteger, b'.') => {
+                    state = State::InFraction;
+                }
+                (State::InFraction, b'0'..=b'9') => {
+                    // 小数部はD / 10^kという分数として桁数kと一緒に蓄積しておく
+                    saw_digit = true;
+                    let digit = (b - b'0') as u64;
+                    frac_digits = frac_digits
+                        .checked_mul(10)
+                        .and_then(|v| v.checked_add(digit))
+                        .ok_or_else(|| Self::overflow_error(is_negative))?;
+                    frac_len += 1;
+                }
+                _ => {
+                    return Err(ParseError::InvalidDigit { byte: b, index });
+                }
+            }
+        }
+
+        if !saw_digit {
+            return Err(ParseError::Empty);
+        }
+
+        if frac_bits >= 128 {
+            // u128へのシフトでもシフト量オーバーフローになってしまうため、
+            // その手前で打ち切ってオーバーフロー扱いにする
+            return Err(Self::overflow_error(is_negative));
+        }
+
+        // D / 10^k を frac_bitsビットの2進小数に丸める（四捨五入）
+        // frac = (D << frac_bits + 10^k / 2) / 10^k
+        // frac_bitsはBITS==64のとき64まであり得る（Q0.64）ため、u64のシフトでは
+        // シフト量オーバーフローになってしまう。u128に広げて計算する
+        let denom: u128 = 10u128
+            .checked_pow(frac_len)
+            .ok_or_else(|| Self::overflow_error(is_negative))?;
+        let numerator: u128 = (frac_digits as u128) << frac_bits;
+        let mut frac: u128 = (numerator + denom / 2) / denom;
+
+        if frac == 1u128 << frac_bits {
+            // 丸めた結果が1.0になった場合は整数部へ繰り上げる
+            int_part = int_part
+                .checked_add(1)
+                .ok_or_else(|| Self::overflow_error(is_negative))?;
+            frac = 0;
+        }
+
+        let limit = if is_negative { Self::SIGN_BIT } else { Self::MAX } as u128;
+
+        // int_partをそのままfrac_bits分左シフトするとu128の範囲を超えて
+        // 静かにラップしてしまうことがあるため、シフト前にint_part自体の上限を検査する
+        // （limitはu64の範囲に収まるのでlimit >> frac_bitsはオーバーフローしない）
+        if (int_part as u128) > limit >> frac_bits {
+            return Err(Self::overflow_error(is_negative));
+        }
+        let combined = ((int_part as u128) << frac_bits) + frac;
+        if combined > limit {
+            return Err(Self::overflow_error(is_negative));
+        }
+        let combined = combined as u64;
+
+        let bits = if is_negative {
+            Self::negate(combined)
         } else {
-            // 正数の場合
-            // そのまま10進数に変換
-            self.bits.to_string()
+            combined
         };
 
-        value
+        Ok(RInt { bits })
+    }
+
+    // 固定小数点数（整数部がBITS-frac_bitsビット、小数部がfrac_bitsビット）として10進表記の文字列に変換する
+    pub fn to_string_fixed(&self, frac_bits: u32) -> String {
+        assert!(frac_bits < 128, "frac_bits must be less than 128");
+
+        let negative = self.bits & Self::SIGN_BIT != 0;
+        let magnitude = if negative {
+            Self::negate(self.bits)
+        } else {
+            self.bits
+        } as u128;
+
+        // frac_bitsはBITS==64のとき64まであり得る（Q0.64）ため、u64のシフトでは
+        // シフト量オーバーフローになってしまう。u128に広げて計算する
+        let mask = (1u128 << frac_bits) - 1;
+        let int_part = (magnitude >> frac_bits) as u64;
+        let mut frac = magnitude & mask;
+
+        // 小数部の下位frac_bitsビットを10倍しては桁あふれした整数部を1桁ずつ取り出す
+        let mut frac_str = String::new();
+        while frac != 0 {
+            frac *= 10;
+            let digit = frac >> frac_bits;
+            frac_str.push((b'0' + digit as u8) as char);
+            frac &= mask;
+        }
+
+        if negative {
+            if frac_str.is_empty() {
+                format!("-{}", int_part)
+            } else {
+                format!("-{}.{}", int_part, frac_str)
+            }
+        } else if frac_str.is_empty() {
+            int_part.to_string()
+        } else {
+            format!("{}.{}", int_part, frac_str)
+        }
     }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
+    type R32 = RInt<32>;
+
     #[test]
     fn test_new() {
-        let r = RInt::from_str("5");
+        let r = R32::from_str("5");
 
-        assert_eq!(r.unwrap().bits, 0b0000_0000_0000_0000_0000_0000_0000_0101);
+        assert_eq!(r.unwrap().bits, 0b101);
     }
 
     #[test]
     fn test_new_minus() {
-        let r = RInt::from_str("-5");
+        let r = R32::from_str("-5");
 
         assert_eq!(r.unwrap().bits, 0b1111_1111_1111_1111_1111_1111_1111_1011);
     }
 
     #[test]
     fn test_add_1() {
-        let r1 = RInt::from_str("5").unwrap(); // 5 ->101
-        let r2 = RInt::from_str("13").unwrap(); // 13 -> 1101
+        let r1 = R32::from_str("5").unwrap(); // 5 ->101
+        let r2 = R32::from_str("13").unwrap(); // 13 -> 1101
 
         let r3 = r1 + r2;
 
-        assert_eq!(r3.bits, 0b0000_0000_0000_0000_0000_0000_0001_0010); // 18 -> 10010
+        assert_eq!(r3.bits, 0b0001_0010); // 18 -> 10010
     }
 
     #[test]
     fn test_add_2() {
-        let r1 = RInt::from_str("-5").unwrap(); // -5 -> 10~//~101
-        let r2 = RInt::from_str("13").unwrap(); // 13 -> 1101
+        let r1 = R32::from_str("-5").unwrap(); // -5 -> 1111...1011
+        let r2 = R32::from_str("13").unwrap(); // 13 -> 1101
 
         let r3 = r1 + r2;
 
-        assert_eq!(r3.bits, 0b0000_0000_0000_0000_0000_0000_0000_1000); // 8 -> 1000
+        assert_eq!(r3.bits, 0b1000); // 8 -> 1000
     }
 
     #[test]
     fn test_add_overflow1() {
-        let r1 = RInt::from_u32(0b1111_1111_1111_1111_1111_1111_1111_1111);
-        let r2 = RInt::from_str("1").unwrap();
+        let r1 = R32::from_bits(0xFFFF_FFFF);
+        let r2 = R32::from_str("1").unwrap();
 
         let r3 = r1 + r2;
 
-        assert_eq!(r3.bits, 0b0000_0000_0000_0000_0000_0000_0000_0000);
+        assert_eq!(r3.bits, 0);
     }
 
     #[test]
     fn test_add_overflow2() {
-        let r1 = RInt::from_u32(0b0111_1111_1111_1111_1111_1111_1111_1111);
-        let r2 = RInt::from_str("1").unwrap();
+        let r1 = R32::from_bits(0x7FFF_FFFF);
+        let r2 = R32::from_str("1").unwrap();
 
         let r3 = r1 + r2;
 
-        assert_eq!(r3.bits, 0b1000_0000_0000_0000_0000_0000_0000_0000);
+        assert_eq!(r3.bits, 0x8000_0000);
     }
 
     #[test]
     fn test_sub() {
-        let r1 = RInt::from_str("5").unwrap(); // 5 ->101
-        let r2 = RInt::from_str("13").unwrap(); // 13 -> 1101
+        let r1 = R32::from_str("5").unwrap(); // 5 ->101
+        let r2 = R32::from_str("13").unwrap(); // 13 -> 1101
 
         let r3 = r1 - r2;
 
@@ -223,35 +776,511 @@ mod tests {
 
     #[test]
     fn test_mul() {
-        let r1 = RInt::from_str("5").unwrap(); // 5 ->101
-        let r2 = RInt::from_str("13").unwrap(); // 13 -> 1101
+        let r1 = R32::from_str("5").unwrap(); // 5 ->101
+        let r2 = R32::from_str("13").unwrap(); // 13 -> 1101
 
         let r3 = r1 * r2;
 
-        assert_eq!(r3.bits, 0b0000_0000_0000_0000_0000_0000_0100_0001); // 65 -> 1000001
+        assert_eq!(r3.bits, 0b0100_0001); // 65 -> 1000001
     }
 
     #[test]
     fn test_div() {
-        let r1 = RInt::from_str("13").unwrap(); // 5 ->1101
-        let r2 = RInt::from_str("5").unwrap(); // 13 -> 101
+        let r1 = R32::from_str("13").unwrap(); // 13 -> 1101
+        let r2 = R32::from_str("5").unwrap(); // 5 -> 101
 
         let r3 = r1 / r2;
 
-        assert_eq!(r3.bits, 0b0000_0000_0000_0000_0000_0000_0010); // 2 -> 10
+        assert_eq!(r3.bits, 0b0010); // 2 -> 10
     }
 
     #[test]
     fn test_to_string() {
-        let r = RInt::from_str("5").unwrap(); // 5 ->101
+        let r = R32::from_str("5").unwrap(); // 5 ->101
 
         assert_eq!(r.to_string(), "5");
     }
 
     #[test]
     fn test_to_string_negative() {
-        let r = RInt::from_str("-25").unwrap(); // 5 ->101
+        let r = R32::from_str("-25").unwrap(); // -25 -> 1111...1110_0111
 
         assert_eq!(r.to_string(), "-25");
     }
+
+    #[test]
+    fn test_from_str_fixed() {
+        let r = R32::from_str_fixed("1.5", 16).unwrap();
+
+        // 1.5 -> 整数部1、小数部0.5 * 2^16 = 0x8000
+        assert_eq!(r.bits, (1 << 16) | 0x8000);
+    }
+
+    #[test]
+    fn test_from_str_fixed_negative() {
+        let r = R32::from_str_fixed("-1.5", 16).unwrap();
+
+        let expected = (!((1u64 << 16) | 0x8000)).wrapping_add(1) & R32::MASK;
+        assert_eq!(r.bits, expected);
+    }
+
+    #[test]
+    fn test_from_str_fixed_rounds_half_up() {
+        // 0.0001 を8ビットの小数部に丸めると1/512（最下位ビットの半分）未満なので0になる
+        let r = R32::from_str_fixed("0.0001", 8).unwrap();
+
+        assert_eq!(r.bits, 0);
+
+        // 0.001953125 はちょうど1/512（最下位ビットの半分）なので切り上げられて1になる
+        let r = R32::from_str_fixed("0.001953125", 8).unwrap();
+
+        assert_eq!(r.bits, 1);
+    }
+
+    #[test]
+    fn test_from_str_fixed_rounds_up_into_integer_part() {
+        // 0.999...は四捨五入で1.0に繰り上がり、小数部は0になる
+        let r = R32::from_str_fixed("0.999999", 8).unwrap();
+
+        assert_eq!(r.bits, 1 << 8);
+    }
+
+    #[test]
+    fn test_to_string_fixed() {
+        let r = R32::from_str_fixed("1.5", 16).unwrap();
+
+        assert_eq!(r.to_string_fixed(16), "1.5");
+    }
+
+    #[test]
+    fn test_to_string_fixed_negative() {
+        let r = R32::from_str_fixed("-1.5", 16).unwrap();
+
+        assert_eq!(r.to_string_fixed(16), "-1.5");
+    }
+
+    #[test]
+    fn test_from_str_fixed_neg_overflow() {
+        // -200はR8の符号付き範囲(-128..=127)を超えるのでNegOverflowになる
+        type R8 = RInt<8>;
+        let r = R8::from_str_fixed("-200", 0);
+
+        assert_eq!(r, Err(ParseError::NegOverflow));
+    }
+
+    #[test]
+    fn test_to_string_fixed_integer() {
+        let r = R32::from_str_fixed("5", 16).unwrap();
+
+        assert_eq!(r.to_string_fixed(16), "5");
+    }
+
+    #[test]
+    fn test_from_str_fixed_long_fraction_does_not_panic() {
+        // frac_lenが19桁を超えると10^frac_lenがu64に収まらなくなりパニックしていたが、
+        // u128で計算するようになったのでこの程度の桁数なら正しく丸められる
+        let r = R32::from_str_fixed("0.000000000000000000001", 16).unwrap();
+
+        assert_eq!(r.bits, 0);
+    }
+
+    #[test]
+    fn test_from_str_fixed_extremely_long_fraction_overflows_instead_of_panicking() {
+        // 10^frac_lenがu128にも収まらないほど桁数が多い場合はパニックではなく
+        // オーバーフローのエラーを返す
+        let r = R32::from_str_fixed("0.0000000000000000000000000000000000000001", 16);
+
+        assert_eq!(r, Err(ParseError::PosOverflow));
+    }
+
+    #[test]
+    fn test_from_str_fixed_frac_bits_64_overflows_instead_of_panicking() {
+        // frac_bits=64はQ0.64に相当し、整数部に1ビットも残らないため
+        // 非ゼロの整数部を持つ値はパニックではなくオーバーフローのエラーになる
+        type R64 = RInt<64>;
+        let r = R64::from_str_fixed("1.5", 64);
+
+        assert_eq!(r, Err(ParseError::PosOverflow));
+    }
+
+    #[test]
+    fn test_to_string_fixed_frac_bits_64_does_not_panic() {
+        // frac_bits=64（Q0.64）では全ビットが小数部として扱われる
+        type R64 = RInt<64>;
+        let r = R64::from_bits(1 << 62);
+
+        assert_eq!(r.to_string_fixed(64), "0.25");
+    }
+
+    #[test]
+    fn test_from_str_fixed_large_int_part_with_wide_frac_bits_overflows() {
+        // int_partをfrac_bits分シフトした時点でu128の範囲を超えうるケースでも、
+        // 静かにラップせず正しくオーバーフローのエラーを返す
+        type R8 = RInt<8>;
+        let r = R8::from_str_fixed("2.0", 127);
+
+        assert_eq!(r, Err(ParseError::PosOverflow));
+    }
+
+    #[test]
+    fn test_div_negative_dividend() {
+        let r1 = R32::from_str("-10").unwrap();
+        let r2 = R32::from_str("2").unwrap();
+
+        let r3 = r1 / r2;
+
+        assert_eq!(r3.to_string(), "-5");
+    }
+
+    #[test]
+    fn test_div_negative_divisor() {
+        let r1 = R32::from_str("10").unwrap();
+        let r2 = R32::from_str("-2").unwrap();
+
+        let r3 = r1 / r2;
+
+        assert_eq!(r3.to_string(), "-5");
+    }
+
+    #[test]
+    fn test_div_both_negative() {
+        let r1 = R32::from_str("-10").unwrap();
+        let r2 = R32::from_str("-2").unwrap();
+
+        let r3 = r1 / r2;
+
+        assert_eq!(r3.to_string(), "5");
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn test_div_by_zero_panics() {
+        let r1 = R32::from_str("10").unwrap();
+        let r2 = R32::from_str("0").unwrap();
+
+        let _ = r1 / r2;
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide with overflow")]
+    fn test_div_min_by_minus_one_panics() {
+        let r1 = R32::from_bits(R32::MIN);
+        let r2 = R32::from_bits(R32::MASK); // -1
+
+        let _ = r1 / r2;
+    }
+
+    #[test]
+    fn test_rem_matches_dividend_sign() {
+        let r1 = R32::from_str("-7").unwrap();
+        let r2 = R32::from_str("2").unwrap();
+
+        let r3 = r1 % r2;
+
+        assert_eq!(r3.to_string(), "-1");
+    }
+
+    #[test]
+    fn test_div_rem_invariant() {
+        // (a / b) * b + (a % b) == a
+        let a = R32::from_str("-7").unwrap();
+        let b = R32::from_str("2").unwrap();
+
+        let quotient = R32::from_str("-7").unwrap() / R32::from_str("2").unwrap();
+        let remainder = R32::from_str("-7").unwrap() % R32::from_str("2").unwrap();
+        let reconstructed = quotient * b + remainder;
+
+        assert_eq!(reconstructed, a);
+    }
+
+    #[test]
+    fn test_ord_negative_less_than_positive() {
+        let r1 = R32::from_str("-5").unwrap();
+        let r2 = R32::from_str("3").unwrap();
+
+        assert!(r1 < r2);
+    }
+
+    #[test]
+    fn test_ord_magnitude_of_negatives() {
+        let r1 = R32::from_str("-10").unwrap();
+        let r2 = R32::from_str("-5").unwrap();
+
+        assert!(r1 < r2);
+    }
+
+    #[test]
+    fn test_eq() {
+        let r1 = R32::from_str("-5").unwrap();
+        let r2 = R32::from_str("-5").unwrap();
+
+        assert_eq!(r1, r2);
+    }
+
+    #[test]
+    fn test_from_str_radix_binary() {
+        let r = R32::from_str_radix("1010", 2).unwrap();
+
+        assert_eq!(r.to_string(), "10");
+    }
+
+    #[test]
+    fn test_from_str_radix_hex() {
+        let r = R32::from_str_radix("ff", 16).unwrap();
+
+        assert_eq!(r.to_string(), "255");
+    }
+
+    #[test]
+    fn test_from_str_radix_hex_prefix() {
+        let r = R32::from_str_radix("0xFF", 16).unwrap();
+
+        assert_eq!(r.to_string(), "255");
+    }
+
+    #[test]
+    fn test_from_str_radix_negative() {
+        let r = R32::from_str_radix("-1010", 2).unwrap();
+
+        assert_eq!(r.to_string(), "-10");
+    }
+
+    #[test]
+    fn test_from_str_radix_neg_overflow() {
+        // -200はR8の符号付き範囲(-128..=127)を超えるのでNegOverflowになる
+        type R8 = RInt<8>;
+        let r = R8::from_str_radix("-200", 10);
+
+        assert_eq!(r, Err(ParseError::NegOverflow));
+    }
+
+    #[test]
+    fn test_from_str_radix_invalid_digit() {
+        let r = R32::from_str_radix("12", 2);
+
+        assert_eq!(r, Err(ParseError::InvalidDigit { byte: b'2', index: 1 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must be in the range 2..=36")]
+    fn test_from_str_radix_invalid_radix() {
+        let _ = R32::from_str_radix("10", 37);
+    }
+
+    #[test]
+    fn test_checked_add_no_overflow() {
+        let r1 = R32::from_str("5").unwrap();
+        let r2 = R32::from_str("13").unwrap();
+
+        assert_eq!(r1.checked_add(r2).unwrap().to_string(), "18");
+    }
+
+    #[test]
+    fn test_checked_add_overflow() {
+        let r1 = R32::from_bits(R32::MAX);
+        let r2 = R32::from_str("1").unwrap();
+
+        assert!(r1.checked_add(r2).is_none());
+    }
+
+    #[test]
+    fn test_checked_sub_overflow() {
+        let r1 = R32::from_bits(R32::MIN);
+        let r2 = R32::from_str("1").unwrap();
+
+        assert!(r1.checked_sub(r2).is_none());
+    }
+
+    #[test]
+    fn test_checked_mul_overflow() {
+        let r1 = R32::from_bits(R32::MAX);
+        let r2 = R32::from_str("2").unwrap();
+
+        assert!(r1.checked_mul(r2).is_none());
+    }
+
+    #[test]
+    fn test_checked_div_by_zero() {
+        let r1 = R32::from_str("10").unwrap();
+        let r2 = R32::from_str("0").unwrap();
+
+        assert!(r1.checked_div(r2).is_none());
+    }
+
+    #[test]
+    fn test_overflowing_add() {
+        let r1 = R32::from_bits(R32::MAX);
+        let r2 = R32::from_str("1").unwrap();
+
+        let (result, overflow) = r1.overflowing_add(r2);
+
+        assert!(overflow);
+        assert_eq!(result.bits, R32::MIN);
+    }
+
+    #[test]
+    fn test_saturating_add_clamps_to_max() {
+        let r1 = R32::from_bits(R32::MAX);
+        let r2 = R32::from_str("1").unwrap();
+
+        assert_eq!(r1.saturating_add(r2).bits, R32::MAX);
+    }
+
+    #[test]
+    fn test_saturating_sub_clamps_to_min() {
+        let r1 = R32::from_bits(R32::MIN);
+        let r2 = R32::from_str("1").unwrap();
+
+        assert_eq!(r1.saturating_sub(r2).bits, R32::MIN);
+    }
+
+    #[test]
+    fn test_saturating_mul_clamps_to_max() {
+        let r1 = R32::from_bits(R32::MAX);
+        let r2 = R32::from_str("2").unwrap();
+
+        assert_eq!(r1.saturating_mul(r2).bits, R32::MAX);
+    }
+
+    #[test]
+    fn test_wrapping_add_matches_add_operator() {
+        let r1 = R32::from_bits(R32::MAX);
+        let r2 = R32::from_str("1").unwrap();
+
+        assert_eq!(r1.wrapping_add(r2).bits, R32::MIN);
+        assert_eq!(r1.wrapping_add(r2), r1 + r2);
+    }
+
+    #[test]
+    fn test_from_str_empty() {
+        assert_eq!(R32::from_str(""), Err(ParseError::Empty));
+    }
+
+    #[test]
+    fn test_from_str_sign_only() {
+        assert_eq!(R32::from_str("-"), Err(ParseError::Empty));
+    }
+
+    #[test]
+    fn test_from_str_invalid_digit_reports_position() {
+        let r = R32::from_str("12x4");
+
+        assert_eq!(
+            r,
+            Err(ParseError::InvalidDigit {
+                byte: b'x',
+                index: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_str_pos_overflow() {
+        let r = R32::from_str("99999999999");
+
+        assert_eq!(r, Err(ParseError::PosOverflow));
+    }
+
+    #[test]
+    fn test_from_str_neg_overflow() {
+        let r = R32::from_str("-99999999999");
+
+        assert_eq!(r, Err(ParseError::NegOverflow));
+    }
+
+    #[test]
+    fn test_from_str_pos_overflow_within_unsigned_range() {
+        // i32::MAXは超えるがu32::MAXには収まる値は、符号付きの範囲でオーバーフロー扱いにする
+        let r = R32::from_str("3000000000");
+
+        assert_eq!(r, Err(ParseError::PosOverflow));
+    }
+
+    #[test]
+    fn test_from_str_neg_overflow_just_past_min() {
+        // i32::MINのさらに1小さい値もNegOverflowになる
+        let r = R32::from_str("-2147483649");
+
+        assert_eq!(r, Err(ParseError::NegOverflow));
+    }
+
+    #[test]
+    fn test_parse_error_display() {
+        let err = ParseError::InvalidDigit {
+            byte: b'x',
+            index: 2,
+        };
+
+        assert_eq!(err.to_string(), "invalid digit 'x' at position 2");
+    }
+
+    #[test]
+    fn test_from_str_trait() {
+        let r: R32 = "5".parse().unwrap();
+
+        assert_eq!(r.to_string(), "5");
+    }
+
+    #[test]
+    fn test_display_trait() {
+        let r = R32::from_str("-25").unwrap();
+
+        assert_eq!(format!("{}", r), "-25");
+    }
+
+    #[test]
+    fn test_zero_and_one() {
+        assert!(R32::zero().is_zero());
+        assert_eq!(R32::one().bits, 1);
+    }
+
+    #[test]
+    fn test_bounded() {
+        assert_eq!(R32::min_value().bits, R32::MIN);
+        assert_eq!(R32::max_value().bits, R32::MAX);
+    }
+
+    #[test]
+    fn test_num_from_str_radix() {
+        let r = <R32 as Num>::from_str_radix("ff", 16).unwrap();
+
+        assert_eq!(r.to_string(), "255");
+    }
+
+    #[test]
+    fn test_checked_add_trait() {
+        let r1 = R32::from_bits(R32::MAX);
+        let r2 = R32::from_str("1").unwrap();
+
+        assert!(CheckedAdd::checked_add(&r1, &r2).is_none());
+    }
+
+    #[test]
+    fn test_saturating_trait() {
+        let r1 = R32::from_bits(R32::MAX);
+        let r2 = R32::from_str("1").unwrap();
+
+        assert_eq!(Saturating::saturating_add(r1, r2).bits, R32::MAX);
+    }
+
+    #[test]
+    fn test_narrow_width_wraps_within_bits() {
+        type R8 = RInt<8>;
+
+        let r1 = R8::from_str("127").unwrap();
+        let r2 = R8::from_str("1").unwrap();
+
+        let r3 = r1 + r2;
+
+        assert_eq!(r3.to_string(), "-128");
+    }
+
+    #[test]
+    fn test_wide_width_beyond_32_bits() {
+        type R64 = RInt<64>;
+
+        let r = R64::from_str("10000000000").unwrap();
+
+        assert_eq!(r.to_string(), "10000000000");
+    }
 }